@@ -2,6 +2,7 @@ use log::error;
 use std::sync::LazyLock;
 use tokio::sync::Mutex;
 
+use crate::models::{OSCMethod, OSCMethodAccessType, OSCMethodValueType, OSCQueryHostInfo, OSCQueryNode};
 use crate::{Error, OSCQueryInitError};
 
 static INITIALIZED: LazyLock<Mutex<bool>> = LazyLock::new(|| Mutex::new(false));
@@ -52,7 +53,114 @@ pub async fn get_vrchat_oscquery_address() -> Option<(String, u16)> {
     Some((oscquery_host, oscquery_port))
 }
 
-pub async fn init(mdns_sidecar_path: &str) -> Result<(), Error> {
+//
+// Talking to the discovered OSCQuery HTTP server
+//
+
+/// Fetches the HostInfo of the discovered VRChat OSCQuery server.
+pub async fn get_remote_host_info() -> Result<OSCQueryHostInfo, Error> {
+    let (host, port) = get_vrchat_oscquery_address()
+        .await
+        .ok_or(Error::InitError(OSCQueryInitError::NotYetInitialized))?;
+    http_get_json(&host, port, "/?HOST_INFO").await
+}
+
+/// Fetches the OSCQuery node at `address` from the discovered VRChat OSCQuery server.
+pub async fn query_node(address: &str) -> Option<OSCQueryNode> {
+    let (host, port) = get_vrchat_oscquery_address().await?;
+    http_get_json(&host, port, address).await.ok()
+}
+
+/// Walks the remote namespace tree and flattens it back into the same [`OSCMethod`] shape used
+/// to register methods locally, so another program's exposed parameters can be enumerated the
+/// same way this crate's own are.
+pub async fn list_methods() -> Vec<OSCMethod> {
+    let Some(root) = query_node("/").await else {
+        return vec![];
+    };
+    let mut methods = vec![];
+    flatten_node(&root, &mut methods);
+    methods
+}
+
+fn flatten_node(node: &OSCQueryNode, out: &mut Vec<OSCMethod>) {
+    if node.contents.is_empty() {
+        if let Some(ad_type) = access_type_from_access(node.access) {
+            let value_type = node
+                .value_type
+                .as_deref()
+                .and_then(value_type_from_osc_type);
+            let value = value_type.and_then(|value_type| {
+                node.value
+                    .first()
+                    .map(|value| stringify_osc_value(value, value_type))
+            });
+            out.push(OSCMethod {
+                address: node.full_path.clone(),
+                ad_type,
+                value_type,
+                value,
+                description: node.description.clone(),
+            });
+        }
+        return;
+    }
+    for child in node.contents.values() {
+        flatten_node(child, out);
+    }
+}
+
+fn access_type_from_access(access: u8) -> Option<OSCMethodAccessType> {
+    match access {
+        1 => Some(OSCMethodAccessType::Read),
+        2 => Some(OSCMethodAccessType::Write),
+        3 => Some(OSCMethodAccessType::ReadWrite),
+        _ => None,
+    }
+}
+
+fn value_type_from_osc_type(osc_type: &str) -> Option<OSCMethodValueType> {
+    match osc_type {
+        "F" | "T" => Some(OSCMethodValueType::Bool),
+        "i" => Some(OSCMethodValueType::Int),
+        "f" => Some(OSCMethodValueType::Float),
+        "s" => Some(OSCMethodValueType::String),
+        _ => None,
+    }
+}
+
+fn stringify_osc_value(value: &serde_json::Value, value_type: OSCMethodValueType) -> String {
+    match value_type {
+        OSCMethodValueType::Bool => value.as_bool().unwrap_or(false).to_string(),
+        OSCMethodValueType::Int | OSCMethodValueType::Float => value.to_string(),
+        OSCMethodValueType::String => value.as_str().unwrap_or_default().to_string(),
+    }
+}
+
+async fn http_get_json<T: serde::de::DeserializeOwned>(
+    host: &str,
+    port: u16,
+    path: &str,
+) -> Result<T, Error> {
+    let url = format!("http://{}:{}{}", host, port, path);
+    let body = reqwest::get(&url)
+        .await
+        .map_err(|e| Error::IO(std::io::Error::new(std::io::ErrorKind::Other, e.to_string())))?
+        .text()
+        .await
+        .map_err(|e| Error::IO(std::io::Error::new(std::io::ErrorKind::Other, e.to_string())))?;
+    serde_json::from_str(&body)
+        .map_err(|e| Error::IO(std::io::Error::new(std::io::ErrorKind::InvalidData, e)))
+}
+
+static DISCOVERY_SHUTDOWN_SENDER: LazyLock<Mutex<Option<tokio::sync::watch::Sender<bool>>>> =
+    LazyLock::new(|| Mutex::default());
+
+pub async fn init(
+    // No longer used now that mDNS discovery is native (see `browse_services`); kept so
+    // existing callers don't need to change their call site.
+    _mdns_sidecar_path: &str,
+) -> Result<(), Error> {
     // Stop if we've already initialized
     {
         let mut initialized = INITIALIZED.lock().await;
@@ -62,18 +170,32 @@ pub async fn init(mdns_sidecar_path: &str) -> Result<(), Error> {
         *initialized = true;
     }
 
-    // Set the MDNS sidecar executable path
-    if let Err(e) = crate::mdns_sidecar::set_exe_path(mdns_sidecar_path.to_string()).await {
-        error!("Could not set the MDNS sidecar executable path: {:#?}", e);
+    if let Err(e) = crate::init_mdns_daemon().await {
+        error!("Could not initialize the MDNS daemon: {:#?}", e);
         *INITIALIZED.lock().await = false;
-        return Err(Error::InitError(e));
+        return Err(e);
     }
+    let daemon = {
+        let guard = crate::MDNS_DAEMON.lock().await;
+        guard
+            .clone()
+            .expect("MDNS daemon should be initialized by init_mdns_daemon")
+    };
 
-    if let Err(e) = crate::mdns_sidecar::mark_client_started().await {
-        error!("Could not start the MDNS Sidecar: {:#?}", e);
-        *INITIALIZED.lock().await = false;
-        return Err(Error::InitError(crate::OSCQueryInitError::MDNSInitFailed));
-    }
+    let (shutdown_sender, shutdown_receiver) = tokio::sync::watch::channel(false);
+    *DISCOVERY_SHUTDOWN_SENDER.lock().await = Some(shutdown_sender);
+    tokio::spawn(browse_services(
+        daemon.clone(),
+        "_osc._udp.local.",
+        false,
+        shutdown_receiver.clone(),
+    ));
+    tokio::spawn(browse_services(
+        daemon,
+        "_oscjson._tcp.local.",
+        true,
+        shutdown_receiver,
+    ));
 
     Ok(())
 }
@@ -86,10 +208,8 @@ pub async fn deinit() -> Result<(), Error> {
             return Err(Error::InitError(OSCQueryInitError::NotYetInitialized));
         }
     }
-    // Stop the MDNS sidecar
-    if let Err(e) = crate::mdns_sidecar::mark_client_stopped().await {
-        error!("Could not stop the MDNS Sidecar: {:#?}", e);
-        return Err(Error::InitError(crate::OSCQueryInitError::MDNSInitFailed));
+    if let Some(sender) = DISCOVERY_SHUTDOWN_SENDER.lock().await.take() {
+        let _ = sender.send(true);
     }
     // Reset state
     {
@@ -102,48 +222,64 @@ pub async fn deinit() -> Result<(), Error> {
     Ok(())
 }
 
-pub(crate) async fn process_log_line(line: String) {
-    if line.starts_with("VRC_OSC_ADDR_DISCOVERY ") {
-        let parts: Vec<&str> = line.split(' ').collect();
-        if parts.len() != 2 {
-            error!("Invalid VRC_OSC_ADDR_DISCOVERY line: {}", line);
-            return;
-        }
-        let addr = parts[1];
-        let addr_parts: Vec<&str> = addr.split(':').collect();
-        if addr_parts.len() != 2 {
-            error!("Invalid VRC_OSC_ADDR_DISCOVERY address: {}", addr);
+/// Watches mDNS for `_osc._udp.local.`/`_oscjson._tcp.local.` records and populates the
+/// discovered VRChat host/port statics directly from the resolved `ServiceInfo`, instead of
+/// scraping a sidecar's stdout for `VRC_OSC_ADDR_DISCOVERY`/`VRC_OSCQUERY_ADDR_DISCOVERY` lines.
+/// Every resolved or removed OSCQuery service is also passed on to [`crate::manager`].
+async fn browse_services(
+    daemon: mdns_sd::ServiceDaemon,
+    service_type: &'static str,
+    is_oscquery: bool,
+    mut shutdown_receiver: tokio::sync::watch::Receiver<bool>,
+) {
+    let receiver = match daemon.browse(service_type) {
+        Ok(receiver) => receiver,
+        Err(e) => {
+            error!("Failed to browse for {}: {}", service_type, e);
             return;
         }
-        let host = addr_parts[0].to_string();
-        let port = addr_parts[1].parse::<u16>();
-        if port.is_err() {
-            error!("Invalid VRC_OSC_ADDR_DISCOVERY port: {}", addr_parts[1]);
-            return;
-        }
-        let port = port.unwrap();
-        *VRC_OSC_HOST.lock().await = Some(host);
-        *VRC_OSC_PORT.lock().await = Some(port);
-    } else if line.starts_with("VRC_OSCQUERY_ADDR_DISCOVERY ") {
-        let parts: Vec<&str> = line.split(' ').collect();
-        if parts.len() != 2 {
-            error!("Invalid VRC_OSCQUERY_ADDR_DISCOVERY line: {}", line);
-            return;
-        }
-        let addr = parts[1];
-        let addr_parts: Vec<&str> = addr.split(':').collect();
-        if addr_parts.len() != 2 {
-            error!("Invalid VRC_OSCQUERY_ADDR_DISCOVERY address: {}", addr);
-            return;
+    };
+    loop {
+        tokio::select! {
+            event = receiver.recv_async() => {
+                let Ok(event) = event else { break };
+                if let mdns_sd::ServiceEvent::ServiceRemoved(_, fullname) = &event {
+                    // mDNS goodbye: drop the service right away instead of waiting for it to go stale
+                    if is_oscquery {
+                        crate::manager::remove_service(fullname).await;
+                    }
+                    continue;
+                }
+                if let mdns_sd::ServiceEvent::ServiceResolved(info) = event {
+                    // VRChat's OSCQuery instance names start with "VRChat-Client-"; only those feed
+                    // the `VRC_*` statics, while the manager tracks every OSCQuery service.
+                    let is_vrchat = info.get_fullname().to_lowercase().contains("vrchat");
+                    let Some(ip) = info.get_addresses().iter().next() else { continue };
+                    let host = ip.to_string();
+                    let port = info.get_port();
+                    if is_oscquery {
+                        if is_vrchat {
+                            *VRC_OSCQUERY_HOST.lock().await = Some(host.clone());
+                            *VRC_OSCQUERY_PORT.lock().await = Some(port);
+                        }
+                        let service_id = info.get_fullname().to_string();
+                        // mDNS re-resolves a live service well before its TTL expires; when that
+                        // re-resolution doesn't change its address, just bump `last_seen` instead
+                        // of re-fetching HostInfo and the node tree over HTTP for no reason.
+                        let already_tracked = crate::manager::mark_seen(&service_id, &host, port).await;
+                        if !already_tracked {
+                            if let Err(e) = crate::manager::discover(service_id, host, port).await {
+                                error!("Failed to register discovered OSCQuery service: {:#?}", e);
+                            }
+                        }
+                    } else if is_vrchat {
+                        *VRC_OSC_HOST.lock().await = Some(host);
+                        *VRC_OSC_PORT.lock().await = Some(port);
+                    }
+                }
+            }
+            _ = shutdown_receiver.changed() => break,
         }
-        let host = addr_parts[0].to_string();
-        let port = addr_parts[1].parse::<u16>();
-        if port.is_err() {
-            error!("Invalid VRC_OSCQUERY_ADDR_DISCOVERY port: {}", addr_parts[1]);
-            return;
-        }
-        let port = port.unwrap();
-        *VRC_OSCQUERY_HOST.lock().await = Some(host);
-        *VRC_OSCQUERY_PORT.lock().await = Some(port);
     }
-}
\ No newline at end of file
+}
+
@@ -0,0 +1,223 @@
+//! Tunnels the loopback-bound OSCQuery HTTP server and OSC UDP port through an outbound
+//! WebSocket connection to a relay host, so a remote peer can reach them without any inbound
+//! port being opened on this machine.
+//!
+//! Unlike [`crate::relay`], which proxies the OSCQuery node tree and OSC messages at the
+//! application level, this multiplexes raw TCP/UDP connections frame-by-frame over a single
+//! WebSocket, leaving `start_oscquery_service`'s loopback binding completely untouched.
+
+use std::collections::HashMap;
+use std::sync::{Arc, LazyLock};
+
+use futures_util::{SinkExt, StreamExt};
+use log::debug;
+use serde::{Deserialize, Serialize};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpStream, UdpSocket};
+use tokio::sync::{mpsc, watch, Mutex};
+use tokio_tungstenite::tungstenite::Message;
+
+use crate::models::Error;
+
+type ConnectionId = u64;
+type WsStream = tokio_tungstenite::WebSocketStream<tokio_tungstenite::MaybeTlsStream<TcpStream>>;
+
+static SHUTDOWN_SENDER: LazyLock<Mutex<Option<watch::Sender<bool>>>> = LazyLock::new(|| Mutex::default());
+static ASSIGNED_ADDRESS: LazyLock<Mutex<Option<String>>> = LazyLock::new(|| Mutex::default());
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+enum RelayProtocol {
+    Tcp,
+    Udp,
+}
+
+/// A frame exchanged with the relay over its WebSocket connection, tagging every byte chunk
+/// with the logical connection id it belongs to.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum RelayFrame {
+    /// Sent once by the relay after we connect, carrying the public address/slug it assigned.
+    Assigned { address: String },
+    /// The relay wants to open a new logical connection to one of our local ports.
+    Open { id: ConnectionId, protocol: RelayProtocol },
+    Data { id: ConnectionId, payload: Vec<u8> },
+    Close { id: ConnectionId },
+}
+
+/// Connect out to `relay_ws_addr` (falling back to the `RELAY_WS_ADDR` environment variable) and
+/// start tunneling `oscquery_port`/`osc_port` to whichever peer the relay pairs this session
+/// with. Returns the public address/slug the relay assigned.
+pub async fn start(
+    relay_ws_addr: Option<&str>,
+    oscquery_port: u16,
+    osc_port: u16,
+) -> Result<String, Error> {
+    let relay_ws_addr = relay_ws_addr
+        .map(|s| s.to_string())
+        .or_else(|| std::env::var("RELAY_WS_ADDR").ok())
+        .ok_or_else(|| {
+            Error::IO(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                "No relay address configured; set RELAY_WS_ADDR or pass one explicitly",
+            ))
+        })?;
+
+    let (mut ws_stream, _) = tokio_tungstenite::connect_async(&relay_ws_addr)
+        .await
+        .map_err(|e| Error::IO(std::io::Error::new(std::io::ErrorKind::Other, e.to_string())))?;
+
+    let address = loop {
+        match ws_stream.next().await {
+            Some(Ok(Message::Text(text))) => {
+                if let Ok(RelayFrame::Assigned { address }) = serde_json::from_str(&text) {
+                    break address;
+                }
+            }
+            Some(Ok(_)) => continue,
+            _ => {
+                return Err(Error::IO(std::io::Error::new(
+                    std::io::ErrorKind::UnexpectedEof,
+                    "Relay closed the connection before assigning an address",
+                )))
+            }
+        }
+    };
+    *ASSIGNED_ADDRESS.lock().await = Some(address.clone());
+
+    let (shutdown_sender, shutdown_receiver) = watch::channel(false);
+    *SHUTDOWN_SENDER.lock().await = Some(shutdown_sender);
+    tokio::spawn(run_session(ws_stream, oscquery_port, osc_port, shutdown_receiver));
+
+    Ok(address)
+}
+
+pub async fn stop() -> Result<(), Error> {
+    if let Some(sender) = SHUTDOWN_SENDER.lock().await.take() {
+        let _ = sender.send(true);
+    }
+    *ASSIGNED_ADDRESS.lock().await = None;
+    Ok(())
+}
+
+async fn run_session(
+    mut ws_stream: WsStream,
+    oscquery_port: u16,
+    osc_port: u16,
+    mut shutdown_receiver: watch::Receiver<bool>,
+) {
+    let connections: Arc<Mutex<HashMap<ConnectionId, mpsc::Sender<Vec<u8>>>>> =
+        Arc::new(Mutex::new(HashMap::new()));
+    let (frame_tx, mut frame_rx) = mpsc::channel::<RelayFrame>(64);
+
+    loop {
+        tokio::select! {
+            incoming = ws_stream.next() => {
+                let Some(Ok(message)) = incoming else { break };
+                let Message::Text(text) = message else { continue };
+                let Ok(frame) = serde_json::from_str::<RelayFrame>(&text) else { continue };
+                match frame {
+                    RelayFrame::Open { id, protocol } => {
+                        tokio::spawn(open_local_connection(
+                            id,
+                            protocol,
+                            oscquery_port,
+                            osc_port,
+                            connections.clone(),
+                            frame_tx.clone(),
+                        ));
+                    }
+                    RelayFrame::Data { id, payload } => {
+                        let sender = connections.lock().await.get(&id).cloned();
+                        if let Some(sender) = sender {
+                            let _ = sender.send(payload).await;
+                        }
+                    }
+                    RelayFrame::Close { id } => {
+                        connections.lock().await.remove(&id);
+                    }
+                    RelayFrame::Assigned { .. } => {}
+                }
+            }
+            frame = frame_rx.recv() => {
+                let Some(frame) = frame else { break };
+                let text = serde_json::to_string(&frame).unwrap();
+                if ws_stream.send(Message::Text(text)).await.is_err() {
+                    break;
+                }
+            }
+            _ = shutdown_receiver.changed() => break,
+        }
+    }
+    debug!("Relay exposure session ended");
+}
+
+/// Opens a fresh local connection for one relay-assigned id. For TCP, bytes are copied both ways:
+/// local reads become outbound `Data` frames, and frames addressed to this id are written back to
+/// the local socket. For UDP, frames addressed to this id are only sent on to the local OSC port;
+/// nothing is read back from it.
+async fn open_local_connection(
+    id: ConnectionId,
+    protocol: RelayProtocol,
+    oscquery_port: u16,
+    osc_port: u16,
+    connections: Arc<Mutex<HashMap<ConnectionId, mpsc::Sender<Vec<u8>>>>>,
+    frame_tx: mpsc::Sender<RelayFrame>,
+) {
+    match protocol {
+        RelayProtocol::Tcp => {
+            let Ok(stream) = TcpStream::connect(("127.0.0.1", oscquery_port)).await else {
+                let _ = frame_tx.send(RelayFrame::Close { id }).await;
+                return;
+            };
+            let (mut read_half, mut write_half) = stream.into_split();
+            let (tx, mut rx) = mpsc::channel::<Vec<u8>>(16);
+            connections.lock().await.insert(id, tx);
+
+            let read_frame_tx = frame_tx.clone();
+            tokio::spawn(async move {
+                let mut buf = [0u8; 4096];
+                loop {
+                    match read_half.read(&mut buf).await {
+                        Ok(0) | Err(_) => break,
+                        Ok(n) => {
+                            if read_frame_tx
+                                .send(RelayFrame::Data { id, payload: buf[..n].to_vec() })
+                                .await
+                                .is_err()
+                            {
+                                break;
+                            }
+                        }
+                    }
+                }
+                let _ = read_frame_tx.send(RelayFrame::Close { id }).await;
+            });
+
+            while let Some(data) = rx.recv().await {
+                if write_half.write_all(&data).await.is_err() {
+                    break;
+                }
+            }
+            connections.lock().await.remove(&id);
+        }
+        RelayProtocol::Udp => {
+            let Ok(socket) = UdpSocket::bind("127.0.0.1:0").await else {
+                let _ = frame_tx.send(RelayFrame::Close { id }).await;
+                return;
+            };
+            if socket.connect(("127.0.0.1", osc_port)).await.is_err() {
+                let _ = frame_tx.send(RelayFrame::Close { id }).await;
+                return;
+            }
+            let (tx, mut rx) = mpsc::channel::<Vec<u8>>(16);
+            connections.lock().await.insert(id, tx);
+            while let Some(data) = rx.recv().await {
+                if socket.send(&data).await.is_err() {
+                    break;
+                }
+            }
+            connections.lock().await.remove(&id);
+        }
+    }
+}
@@ -2,8 +2,13 @@
 extern crate lazy_static;
 
 pub mod client;
+pub mod expose;
+pub mod manager;
 pub mod models;
+pub mod mqtt_bridge;
+pub mod relay;
 pub mod server;
+pub mod tunnel;
 use mdns_sd::ServiceDaemon;
 pub use models::*;
 use tokio::sync::Mutex;
@@ -0,0 +1,186 @@
+//! Tracks every OSCQuery service discovered on the network, so an application can enumerate,
+//! refresh, and query more than one peer at a time instead of assuming a single VRChat endpoint.
+
+use std::collections::HashMap;
+use std::sync::LazyLock;
+use std::time::{Duration, Instant};
+
+use log::debug;
+use tokio::sync::{broadcast, Mutex};
+
+use crate::models::{Error, OSCQueryHostInfo, OSCQueryNode};
+
+/// How long a service can go without being re-discovered or refreshed before it's considered
+/// gone and is dropped from the registry.
+const STALE_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Identifies a discovered service, e.g. its mDNS instance name. Re-discovering the same id
+/// updates the existing entry rather than creating a duplicate.
+pub type ServiceId = String;
+
+#[derive(Debug, Clone)]
+pub struct DiscoveredService {
+    pub id: ServiceId,
+    /// Address of the service's OSCQuery HTTP server, as resolved by mDNS.
+    pub oscquery_host: String,
+    pub oscquery_port: u16,
+    pub host_info: OSCQueryHostInfo,
+    pub root_node: Option<OSCQueryNode>,
+    pub last_seen: Instant,
+}
+
+#[derive(Debug, Clone)]
+pub enum ServiceEvent {
+    Added(DiscoveredService),
+    Updated(DiscoveredService),
+    Removed(ServiceId),
+}
+
+static SERVICES: LazyLock<Mutex<HashMap<ServiceId, DiscoveredService>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+static EVENTS: LazyLock<broadcast::Sender<ServiceEvent>> = LazyLock::new(|| broadcast::channel(64).0);
+static STALENESS_CHECKER_STARTED: LazyLock<Mutex<bool>> = LazyLock::new(|| Mutex::new(false));
+
+pub async fn list_services() -> Vec<DiscoveredService> {
+    SERVICES.lock().await.values().cloned().collect()
+}
+
+/// Subscribe to add/update/remove events for the whole registry.
+pub fn subscribe() -> broadcast::Receiver<ServiceEvent> {
+    EVENTS.subscribe()
+}
+
+/// The cached OSCQuery node at `path` for a given service, if the service and the node are both
+/// known.
+pub async fn query_node(service_id: &str, path: &str) -> Option<OSCQueryNode> {
+    let services = SERVICES.lock().await;
+    let service = services.get(service_id)?;
+    let root_node = service.root_node.as_ref()?;
+    let mut current_node = root_node;
+    for part in path.split('/') {
+        if part.is_empty() {
+            continue;
+        }
+        current_node = current_node.contents.get(part)?;
+    }
+    Some(current_node.clone())
+}
+
+/// Re-fetch a known service's HostInfo and node tree over HTTP.
+pub async fn refresh(service_id: &str) -> Result<(), Error> {
+    let (oscquery_host, oscquery_port) = {
+        let services = SERVICES.lock().await;
+        let service = services.get(service_id).ok_or_else(|| {
+            Error::IO(std::io::Error::new(
+                std::io::ErrorKind::NotFound,
+                "Unknown service",
+            ))
+        })?;
+        (service.oscquery_host.clone(), service.oscquery_port)
+    };
+    discover(service_id.to_string(), oscquery_host, oscquery_port).await
+}
+
+/// Called whenever a service is (re)discovered, e.g. from an mDNS browse event or [`refresh`]:
+/// fetches its HostInfo and node tree over HTTP and
+/// inserts/updates the registry, deduplicating by `id` so a restarted peer refreshes its
+/// existing entry instead of creating a duplicate.
+pub(crate) async fn discover(
+    id: ServiceId,
+    oscquery_host: String,
+    oscquery_port: u16,
+) -> Result<(), Error> {
+    ensure_staleness_checker().await;
+    let host_info = fetch_host_info(&oscquery_host, oscquery_port).await?;
+    let root_node = fetch_root_node(&oscquery_host, oscquery_port).await.ok();
+    let service = DiscoveredService {
+        id: id.clone(),
+        oscquery_host,
+        oscquery_port,
+        host_info,
+        root_node,
+        last_seen: Instant::now(),
+    };
+    let mut services = SERVICES.lock().await;
+    let event = if services.contains_key(&id) {
+        ServiceEvent::Updated(service.clone())
+    } else {
+        ServiceEvent::Added(service.clone())
+    };
+    services.insert(id, service);
+    let _ = EVENTS.send(event);
+    Ok(())
+}
+
+/// Marks a known service as still present without re-fetching it over HTTP, e.g. on a repeat
+/// mDNS announcement that re-resolves the same address. Returns `false` (and leaves the
+/// registry untouched) if the service is unknown or its address has changed since it was last
+/// seen, so the caller can fall back to a full [`discover`].
+pub(crate) async fn mark_seen(id: &str, oscquery_host: &str, oscquery_port: u16) -> bool {
+    let mut services = SERVICES.lock().await;
+    match services.get_mut(id) {
+        Some(service) if service.oscquery_host == oscquery_host && service.oscquery_port == oscquery_port => {
+            service.last_seen = Instant::now();
+            true
+        }
+        _ => false,
+    }
+}
+
+/// Removes a service immediately, e.g. on an mDNS goodbye packet.
+pub(crate) async fn remove_service(id: &str) {
+    let removed = SERVICES.lock().await.remove(id);
+    if removed.is_some() {
+        let _ = EVENTS.send(ServiceEvent::Removed(id.to_string()));
+    }
+}
+
+async fn ensure_staleness_checker() {
+    {
+        let mut started = STALENESS_CHECKER_STARTED.lock().await;
+        if *started {
+            return;
+        }
+        *started = true;
+    }
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(Duration::from_secs(5));
+        loop {
+            interval.tick().await;
+            let stale_ids: Vec<ServiceId> = {
+                let services = SERVICES.lock().await;
+                services
+                    .values()
+                    .filter(|service| service.last_seen.elapsed() > STALE_TIMEOUT)
+                    .map(|service| service.id.clone())
+                    .collect()
+            };
+            for id in stale_ids {
+                debug!("Discovered service timed out: {}", id);
+                remove_service(&id).await;
+            }
+        }
+    });
+}
+
+async fn fetch_host_info(host: &str, port: u16) -> Result<OSCQueryHostInfo, Error> {
+    let body = http_get(host, port, "/?HOST_INFO").await?;
+    serde_json::from_str(&body)
+        .map_err(|e| Error::IO(std::io::Error::new(std::io::ErrorKind::InvalidData, e)))
+}
+
+async fn fetch_root_node(host: &str, port: u16) -> Result<OSCQueryNode, Error> {
+    let body = http_get(host, port, "/").await?;
+    serde_json::from_str(&body)
+        .map_err(|e| Error::IO(std::io::Error::new(std::io::ErrorKind::InvalidData, e)))
+}
+
+async fn http_get(host: &str, port: u16, path: &str) -> Result<String, Error> {
+    let url = format!("http://{}:{}{}", host, port, path);
+    reqwest::get(&url)
+        .await
+        .map_err(|e| Error::IO(std::io::Error::new(std::io::ErrorKind::Other, e.to_string())))?
+        .text()
+        .await
+        .map_err(|e| Error::IO(std::io::Error::new(std::io::ErrorKind::Other, e.to_string())))
+}
@@ -37,7 +37,7 @@ impl OSCMethodValueType {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct OSCMethod {
     pub address: String,
@@ -59,7 +59,9 @@ pub struct OSCQueryNode {
     pub access: u8,
     #[serde(skip_serializing_if = "Map::is_empty")]
     pub contents: Map<String, OSCQueryNode>,
-    #[serde(alias = "TYPE", skip_serializing_if = "Option::is_none")]
+    // OSCQuery uses the key "TYPE"; `rename_all` would otherwise turn this into "VALUE_TYPE"
+    // on the way out, while still only aliasing "TYPE" (not renaming) on the way in.
+    #[serde(rename = "TYPE", skip_serializing_if = "Option::is_none")]
     pub value_type: Option<String>,
     #[serde(skip_serializing_if = "Vec::is_empty")]
     pub value: Vec<serde_json::Value>,
@@ -80,6 +82,13 @@ pub struct OSCQueryHostInfo {
     pub osc_transport: String,
     pub osc_ip: String,
     pub osc_port: u16,
+    /// Address of the WebSocket endpoint that serves LISTEN/IGNORE subscriptions for live
+    /// parameter updates. Not present in `HOST_INFO` served by other OSCQuery implementations
+    /// (e.g. VRChat), so this must stay optional.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ws_ip: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ws_port: Option<u16>,
     pub extensions: Map<String, bool>,
 }
 
@@ -88,5 +97,20 @@ pub enum Error {
     IO(std::io::Error),
     LocalIpUnavailable(local_ip_address::Error),
     InitError(OSCQueryInitError),
-    IPV4Unavailable(), 
+    IPV4Unavailable(),
+}
+
+/// Which way OSC traffic flows across a [`crate::tunnel`] forward.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub enum ForwardDirection {
+    /// OSC packets received on the local port are forwarded to the remote peer.
+    LocalToRemote,
+    /// OSC packets received by the remote peer are forwarded to the local port.
+    RemoteToLocal,
+}
+
+/// The transport being relayed over a [`crate::tunnel`] forward.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub enum ForwardProtocol {
+    Udp,
 }
@@ -0,0 +1,284 @@
+use std::collections::{HashMap, HashSet};
+use std::time::Duration;
+
+use log::{debug, error, warn};
+use rumqttc::{AsyncClient, Event, MqttOptions, Packet, QoS};
+use std::sync::LazyLock;
+use tokio::net::UdpSocket;
+use tokio::sync::Mutex;
+
+use crate::models::{Error, OSCMethodAccessType, OSCMethodValueType};
+
+static INITIALIZED: LazyLock<Mutex<bool>> = LazyLock::new(|| Mutex::new(false));
+static SHUTDOWN_SENDER: LazyLock<Mutex<Option<tokio::sync::watch::Sender<bool>>>> =
+    LazyLock::new(|| Mutex::default());
+static LAST_PUBLISHED_VALUES: LazyLock<Mutex<HashMap<String, Option<String>>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+/// Addresses we've already issued an MQTT subscription for, so methods registered after `init`
+/// (via `server::add_osc_method`) still get mirrored instead of only those present at startup.
+static SUBSCRIBED_ADDRESSES: LazyLock<Mutex<HashSet<String>>> = LazyLock::new(|| Mutex::new(HashSet::new()));
+
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Start mirroring registered OSC methods to an MQTT broker.
+///
+/// `mqtt_url` is a `mqtt://host:port/topic-prefix` URL; the path (if any) is used as the topic
+/// prefix that every OSC address is nested under, e.g. `mqtt://localhost:1883/oyasumivr` maps
+/// `/avatar/parameters/Foo` to the topic `oyasumivr/avatar/parameters/Foo`.
+pub async fn init(mqtt_url: &str) -> Result<(), Error> {
+    // Ensure single initialization
+    {
+        let mut initialized = INITIALIZED.lock().await;
+        if *initialized {
+            return Ok(());
+        }
+        *initialized = true;
+    }
+
+    let url = match url::Url::parse(mqtt_url) {
+        Ok(url) => url,
+        Err(_) => {
+            *INITIALIZED.lock().await = false;
+            return Err(Error::IO(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                "Invalid MQTT URL",
+            )));
+        }
+    };
+    let host = url.host_str().unwrap_or("127.0.0.1").to_string();
+    let port = url.port().unwrap_or(1883);
+    let topic_prefix = url.path().trim_matches('/').to_string();
+
+    let mut mqtt_options = MqttOptions::new(
+        format!("oyasumivr-oscquery-{}", std::process::id()),
+        host,
+        port,
+    );
+    mqtt_options.set_keep_alive(Duration::from_secs(5));
+    let (client, event_loop) = AsyncClient::new(mqtt_options, 64);
+
+    // Subscribe to every writable method registered so far; `run_publish_loop` keeps this in
+    // sync with methods registered later via `server::add_osc_method`.
+    sync_subscriptions(&client, &topic_prefix).await;
+
+    let (shutdown_sender, shutdown_receiver) = tokio::sync::watch::channel(false);
+    {
+        let mut sender_ref = SHUTDOWN_SENDER.lock().await;
+        *sender_ref = Some(shutdown_sender);
+    }
+
+    // Drives the MQTT connection, reconnecting automatically as `rumqttc` surfaces errors
+    tokio::spawn(run_event_loop(
+        event_loop,
+        topic_prefix.clone(),
+        shutdown_receiver.clone(),
+    ));
+    // Polls registered OSC methods for value changes and publishes them to MQTT
+    tokio::spawn(run_publish_loop(client, topic_prefix, shutdown_receiver));
+
+    Ok(())
+}
+
+pub async fn deinit() -> Result<(), Error> {
+    {
+        let initialized = INITIALIZED.lock().await;
+        if !*initialized {
+            return Ok(());
+        }
+    }
+    {
+        let mut sender_ref = SHUTDOWN_SENDER.lock().await;
+        if let Some(sender) = sender_ref.take() {
+            let _ = sender.send(true);
+        }
+    }
+    {
+        *LAST_PUBLISHED_VALUES.lock().await = HashMap::new();
+        *SUBSCRIBED_ADDRESSES.lock().await = HashSet::new();
+        *INITIALIZED.lock().await = false;
+    }
+    Ok(())
+}
+
+/// Subscribes to any writable method that doesn't already have an MQTT subscription, so methods
+/// registered after `init` via `server::add_osc_method` are mirrored too.
+async fn sync_subscriptions(client: &AsyncClient, topic_prefix: &str) {
+    let methods = crate::server::get_osc_methods().await;
+    let mut subscribed = SUBSCRIBED_ADDRESSES.lock().await;
+    for method in methods {
+        if !matches!(
+            method.ad_type,
+            OSCMethodAccessType::Write | OSCMethodAccessType::ReadWrite
+        ) {
+            continue;
+        }
+        if subscribed.contains(&method.address) {
+            continue;
+        }
+        let topic = address_to_topic(topic_prefix, &method.address);
+        if let Err(e) = client.subscribe(topic, QoS::AtMostOnce).await {
+            warn!("Failed to subscribe to MQTT topic for {}: {}", method.address, e);
+            continue;
+        }
+        subscribed.insert(method.address);
+    }
+}
+
+fn address_to_topic(topic_prefix: &str, address: &str) -> String {
+    let address = address.trim_start_matches('/');
+    if topic_prefix.is_empty() {
+        address.to_string()
+    } else {
+        format!("{}/{}", topic_prefix, address)
+    }
+}
+
+fn topic_to_address(topic_prefix: &str, topic: &str) -> String {
+    let stripped = topic
+        .strip_prefix(topic_prefix)
+        .unwrap_or(topic)
+        .trim_start_matches('/');
+    format!("/{}", stripped)
+}
+
+/// Keeps the MQTT connection alive and relays inbound publishes on subscribed topics to VRChat
+/// as OSC messages. `rumqttc` automatically reconnects on its own when the event loop is polled
+/// in a loop like this, so the broker can be restarted without us tearing the bridge down.
+async fn run_event_loop(
+    mut event_loop: rumqttc::EventLoop,
+    topic_prefix: String,
+    mut shutdown_receiver: tokio::sync::watch::Receiver<bool>,
+) {
+    loop {
+        tokio::select! {
+            notification = event_loop.poll() => {
+                match notification {
+                    Ok(Event::Incoming(Packet::Publish(publish))) => {
+                        handle_inbound_publish(&topic_prefix, publish.topic, publish.payload.to_vec()).await;
+                    }
+                    // A (re)connect starts a clean session, so let `run_publish_loop` subscribe again
+                    Ok(Event::Incoming(Packet::ConnAck(_))) => {
+                        SUBSCRIBED_ADDRESSES.lock().await.clear();
+                    }
+                    Ok(_) => {}
+                    Err(e) => {
+                        error!("MQTT connection error, retrying: {}", e);
+                        tokio::time::sleep(Duration::from_secs(1)).await;
+                    }
+                }
+            }
+            _ = shutdown_receiver.changed() => {
+                break;
+            }
+        }
+    }
+}
+
+async fn handle_inbound_publish(topic_prefix: &str, topic: String, payload: Vec<u8>) {
+    let address = topic_to_address(topic_prefix, &topic);
+    let methods = crate::server::get_osc_methods().await;
+    let method = match methods.iter().find(|m| m.address == address) {
+        Some(method) => method,
+        None => {
+            debug!("Ignoring MQTT message for unknown OSC address: {}", address);
+            return;
+        }
+    };
+    let value_type = match method.value_type {
+        Some(value_type) => value_type,
+        None => OSCMethodValueType::String,
+    };
+    let json_value: serde_json::Value = match serde_json::from_slice(&payload) {
+        Ok(value) => value,
+        Err(_) => serde_json::Value::String(String::from_utf8_lossy(&payload).to_string()),
+    };
+    let osc_arg = match value_type {
+        OSCMethodValueType::Bool => rosc::OscType::Bool(json_value.as_bool().unwrap_or(false)),
+        OSCMethodValueType::Int => rosc::OscType::Int(json_value.as_i64().unwrap_or(0) as i32),
+        OSCMethodValueType::Float => {
+            rosc::OscType::Float(json_value.as_f64().unwrap_or(0.0) as f32)
+        }
+        OSCMethodValueType::String => {
+            rosc::OscType::String(json_value.as_str().unwrap_or_default().to_string())
+        }
+    };
+    let osc_port = match crate::server::get_osc_port().await {
+        Some(port) => port,
+        None => return,
+    };
+    let packet = rosc::OscPacket::Message(rosc::OscMessage {
+        addr: address,
+        args: vec![osc_arg],
+    });
+    let bytes = match rosc::encoder::encode(&packet) {
+        Ok(bytes) => bytes,
+        Err(e) => {
+            error!("Failed to encode OSC message for MQTT bridge: {:?}", e);
+            return;
+        }
+    };
+    match UdpSocket::bind("0.0.0.0:0").await {
+        Ok(socket) => {
+            if let Err(e) = socket.send_to(&bytes, ("127.0.0.1", osc_port)).await {
+                error!("Failed to send OSC message from MQTT bridge: {}", e);
+            }
+        }
+        Err(e) => error!("Failed to open UDP socket for MQTT bridge: {}", e),
+    }
+}
+
+async fn run_publish_loop(
+    client: AsyncClient,
+    topic_prefix: String,
+    mut shutdown_receiver: tokio::sync::watch::Receiver<bool>,
+) {
+    let mut interval = tokio::time::interval(POLL_INTERVAL);
+    loop {
+        tokio::select! {
+            _ = interval.tick() => {
+                sync_subscriptions(&client, &topic_prefix).await;
+                publish_changed_values(&client, &topic_prefix).await;
+            }
+            _ = shutdown_receiver.changed() => {
+                break;
+            }
+        }
+    }
+}
+
+async fn publish_changed_values(client: &AsyncClient, topic_prefix: &str) {
+    let methods = crate::server::get_osc_methods().await;
+    let mut last_values = LAST_PUBLISHED_VALUES.lock().await;
+    for method in methods {
+        if !matches!(
+            method.ad_type,
+            OSCMethodAccessType::Read | OSCMethodAccessType::ReadWrite
+        ) {
+            continue;
+        }
+        let value_type = match method.value_type {
+            Some(value_type) => value_type,
+            None => continue,
+        };
+        let previous = last_values.get(&method.address).cloned().flatten();
+        if previous == method.value {
+            continue;
+        }
+        last_values.insert(method.address.clone(), method.value.clone());
+        let payload = match (&method.value, value_type) {
+            (Some(value), OSCMethodValueType::Bool) => (value == "true").to_string(),
+            (Some(value), OSCMethodValueType::Int | OSCMethodValueType::Float) => value.clone(),
+            (Some(value), OSCMethodValueType::String) => {
+                serde_json::to_string(value).unwrap_or_default()
+            }
+            (None, _) => continue,
+        };
+        let topic = address_to_topic(topic_prefix, &method.address);
+        if let Err(e) = client
+            .publish(topic, QoS::AtMostOnce, false, payload)
+            .await
+        {
+            error!("Failed to publish MQTT message for {}: {}", method.address, e);
+        }
+    }
+}
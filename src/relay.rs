@@ -0,0 +1,240 @@
+//! Relays OSCQuery HostInfo, the node tree, and OSC traffic to a remote peer through a public
+//! relay server, so two instances can discover and talk to each other across the internet
+//! without either side needing an open inbound port.
+
+use std::sync::LazyLock;
+use std::time::Duration;
+
+use futures_util::{SinkExt, StreamExt};
+use log::{debug, error};
+use serde::{Deserialize, Serialize};
+use tokio::net::UdpSocket;
+use tokio::sync::{watch, Mutex};
+use tokio_tungstenite::tungstenite::Message;
+
+use crate::models::{Error, OSCQueryHostInfo, OSCQueryInitError, OSCQueryNode};
+
+const RECONNECT_BACKOFF: [Duration; 5] = [
+    Duration::from_secs(1),
+    Duration::from_secs(2),
+    Duration::from_secs(5),
+    Duration::from_secs(10),
+    Duration::from_secs(30),
+];
+
+static INITIALIZED: LazyLock<Mutex<bool>> = LazyLock::new(|| Mutex::new(false));
+static SHUTDOWN_SENDER: LazyLock<Mutex<Option<watch::Sender<bool>>>> = LazyLock::new(|| Mutex::default());
+static ASSIGNED_ADDRESS: LazyLock<Mutex<Option<String>>> = LazyLock::new(|| Mutex::default());
+static RELAY_URL: LazyLock<Mutex<Option<String>>> = LazyLock::new(|| Mutex::default());
+
+/// A message exchanged with the relay server over its WebSocket connection.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum RelayMessage {
+    /// Sent once on connect; the relay answers with `Registered`.
+    Register,
+    Registered { slug: String },
+    HostInfoRequest,
+    HostInfoResponse { host_info: OSCQueryHostInfo },
+    NodeRequest { path: String },
+    NodeResponse { node: Option<OSCQueryNode> },
+    /// A raw (`rosc`-encoded) OSC packet being relayed in either direction.
+    Osc { payload: Vec<u8> },
+}
+
+/// Connect to `relay_url` and keep exchanging OSCQuery/OSC traffic with it until [`disconnect`]
+/// is called, reconnecting with backoff if the connection drops. Returns the stable slug the
+/// relay assigned this session, which a peer can use to address it.
+pub async fn connect(relay_url: &str) -> Result<String, Error> {
+    {
+        let mut initialized = INITIALIZED.lock().await;
+        if *initialized {
+            return Err(Error::InitError(OSCQueryInitError::AlreadyInitialized));
+        }
+        *initialized = true;
+    }
+    *RELAY_URL.lock().await = Some(relay_url.to_string());
+
+    let (ws_stream, slug) = match connect_once(relay_url).await {
+        Ok(result) => result,
+        Err(e) => {
+            *INITIALIZED.lock().await = false;
+            return Err(e);
+        }
+    };
+    *ASSIGNED_ADDRESS.lock().await = Some(slug.clone());
+
+    let (shutdown_sender, shutdown_receiver) = watch::channel(false);
+    *SHUTDOWN_SENDER.lock().await = Some(shutdown_sender);
+    tokio::spawn(run_relay_session(relay_url.to_string(), ws_stream, shutdown_receiver));
+
+    Ok(slug)
+}
+
+pub async fn disconnect() -> Result<(), Error> {
+    {
+        let initialized = INITIALIZED.lock().await;
+        if !*initialized {
+            return Err(Error::InitError(OSCQueryInitError::NotYetInitialized));
+        }
+    }
+    if let Some(sender) = SHUTDOWN_SENDER.lock().await.take() {
+        let _ = sender.send(true);
+    }
+    *ASSIGNED_ADDRESS.lock().await = None;
+    *RELAY_URL.lock().await = None;
+    *INITIALIZED.lock().await = false;
+    Ok(())
+}
+
+/// The slug the relay assigned this session, if connected.
+pub async fn assigned_address() -> Option<String> {
+    ASSIGNED_ADDRESS.lock().await.clone()
+}
+
+async fn connect_once(
+    relay_url: &str,
+) -> Result<
+    (
+        tokio_tungstenite::WebSocketStream<tokio_tungstenite::MaybeTlsStream<tokio::net::TcpStream>>,
+        String,
+    ),
+    Error,
+> {
+    let (mut ws_stream, _) = tokio_tungstenite::connect_async(relay_url)
+        .await
+        .map_err(|e| Error::IO(std::io::Error::new(std::io::ErrorKind::Other, e.to_string())))?;
+    send_message(&mut ws_stream, &RelayMessage::Register).await?;
+    loop {
+        match ws_stream.next().await {
+            Some(Ok(Message::Text(text))) => {
+                if let Ok(RelayMessage::Registered { slug }) = serde_json::from_str(&text) {
+                    debug!("Relay assigned address: {}", slug);
+                    return Ok((ws_stream, slug));
+                }
+            }
+            Some(Ok(_)) => continue,
+            Some(Err(e)) => {
+                return Err(Error::IO(std::io::Error::new(std::io::ErrorKind::Other, e.to_string())))
+            }
+            None => {
+                return Err(Error::IO(std::io::Error::new(
+                    std::io::ErrorKind::UnexpectedEof,
+                    "Relay closed the connection before assigning an address",
+                )))
+            }
+        }
+    }
+}
+
+async fn run_relay_session(
+    relay_url: String,
+    mut ws_stream: tokio_tungstenite::WebSocketStream<
+        tokio_tungstenite::MaybeTlsStream<tokio::net::TcpStream>,
+    >,
+    mut shutdown_receiver: watch::Receiver<bool>,
+) {
+    let mut backoff_index = 0usize;
+    loop {
+        tokio::select! {
+            message = ws_stream.next() => {
+                match message {
+                    Some(Ok(message)) => {
+                        backoff_index = 0;
+                        handle_relay_message(&mut ws_stream, message).await;
+                    }
+                    Some(Err(e)) => {
+                        error!("Relay connection error: {}", e);
+                        break;
+                    }
+                    None => {
+                        debug!("Relay connection closed");
+                        break;
+                    }
+                }
+            }
+            _ = shutdown_receiver.changed() => return,
+        }
+    }
+
+    // Connection dropped; reconnect with backoff until shutdown is requested.
+    loop {
+        let delay = RECONNECT_BACKOFF[backoff_index.min(RECONNECT_BACKOFF.len() - 1)];
+        backoff_index += 1;
+        tokio::select! {
+            _ = tokio::time::sleep(delay) => {}
+            _ = shutdown_receiver.changed() => return,
+        }
+        match connect_once(&relay_url).await {
+            Ok((new_stream, slug)) => {
+                *ASSIGNED_ADDRESS.lock().await = Some(slug);
+                Box::pin(run_relay_session(relay_url, new_stream, shutdown_receiver)).await;
+                return;
+            }
+            Err(e) => error!("Failed to reconnect to relay, retrying: {:?}", e),
+        }
+    }
+}
+
+async fn handle_relay_message(
+    ws_stream: &mut tokio_tungstenite::WebSocketStream<
+        tokio_tungstenite::MaybeTlsStream<tokio::net::TcpStream>,
+    >,
+    message: Message,
+) {
+    let text = match message {
+        Message::Text(text) => text,
+        Message::Close(_) => return,
+        _ => return,
+    };
+    let relay_message: RelayMessage = match serde_json::from_str(&text) {
+        Ok(message) => message,
+        Err(e) => {
+            error!("Failed to parse relay message: {}", e);
+            return;
+        }
+    };
+    match relay_message {
+        RelayMessage::HostInfoRequest => {
+            if let Some(host_info) = crate::server::get_host_info().await {
+                let _ = send_message(ws_stream, &RelayMessage::HostInfoResponse { host_info }).await;
+            }
+        }
+        RelayMessage::NodeRequest { path } => {
+            let node = crate::server::get_node_for_osc_address(path).await;
+            let _ = send_message(ws_stream, &RelayMessage::NodeResponse { node }).await;
+        }
+        RelayMessage::Osc { payload } => forward_osc_to_local_server(payload).await,
+        RelayMessage::Register | RelayMessage::Registered { .. } | RelayMessage::HostInfoResponse { .. } | RelayMessage::NodeResponse { .. } => {
+            // Not expected from the relay after registration; ignore.
+        }
+    }
+}
+
+/// Deliver an OSC packet relayed from the remote peer to the local OSC server port.
+async fn forward_osc_to_local_server(payload: Vec<u8>) {
+    let Some(osc_port) = crate::server::get_osc_port().await else {
+        return;
+    };
+    match UdpSocket::bind("127.0.0.1:0").await {
+        Ok(socket) => {
+            if let Err(e) = socket.send_to(&payload, ("127.0.0.1", osc_port)).await {
+                error!("Failed to forward relayed OSC packet locally: {}", e);
+            }
+        }
+        Err(e) => error!("Failed to open UDP socket for relay forwarding: {}", e),
+    }
+}
+
+async fn send_message(
+    ws_stream: &mut tokio_tungstenite::WebSocketStream<
+        tokio_tungstenite::MaybeTlsStream<tokio::net::TcpStream>,
+    >,
+    message: &RelayMessage,
+) -> Result<(), Error> {
+    let text = serde_json::to_string(message).unwrap();
+    ws_stream
+        .send(Message::Text(text))
+        .await
+        .map_err(|e| Error::IO(std::io::Error::new(std::io::ErrorKind::Other, e.to_string())))
+}
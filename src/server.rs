@@ -1,9 +1,14 @@
+use std::borrow::Cow;
 use std::collections::HashMap;
 
 use crate::models::{
     Error, OSCMethod, OSCMethodAccessType, OSCMethodValueType, OSCQueryHostInfo, OSCQueryInitError,
     OSCQueryNode,
 };
+use async_tungstenite::tokio::accept_async;
+use async_tungstenite::tungstenite::Message;
+use futures_util::{SinkExt, StreamExt};
+use http_body::{Body as HttpBody, Frame};
 use http_body_util::Full;
 use hyper::body::Bytes;
 use hyper::server::conn::http1;
@@ -11,10 +16,14 @@ use hyper::service::service_fn;
 use hyper::{Request, Response};
 use hyper_util::rt::TokioIo;
 use log::{debug, error};
+use std::collections::HashSet;
 use std::convert::Infallible;
 use std::net::SocketAddr;
+use std::pin::Pin;
 use std::sync::LazyLock;
+use std::task::{Context, Poll};
 use tokio::net::TcpListener;
+use tokio::sync::broadcast;
 use tokio::sync::watch::{channel, Sender};
 use tokio::sync::Mutex;
 
@@ -25,11 +34,21 @@ static OSCQUERY_ROOT_NODE: LazyLock<Mutex<Option<OSCQueryNode>>> = LazyLock::new
 static OSC_PORT: LazyLock<Mutex<Option<u16>>> = LazyLock::new(|| Mutex::default());
 static OSCQUERY_PORT: LazyLock<Mutex<Option<u16>>> = LazyLock::new(|| Mutex::default());
 static OSCQUERY_SHUTDOWN_SENDER: LazyLock<Mutex<Option<Sender<bool>>>> = LazyLock::new(|| Mutex::default());
+static WS_PORT: LazyLock<Mutex<Option<u16>>> = LazyLock::new(|| Mutex::default());
+static WS_SHUTDOWN_SENDER: LazyLock<Mutex<Option<Sender<bool>>>> = LazyLock::new(|| Mutex::default());
+static MDNS_REGISTERED_OSCQUERY: LazyLock<Mutex<Option<String>>> = LazyLock::new(|| Mutex::default());
+static MDNS_REGISTERED_OSC: LazyLock<Mutex<Option<String>>> = LazyLock::new(|| Mutex::default());
+/// Fed by [`set_osc_method_value`]; every open WebSocket connection subscribed to an address
+/// gets the new value pushed to it as soon as it changes.
+static OSC_VALUE_UPDATES: LazyLock<broadcast::Sender<(String, Vec<serde_json::Value>)>> =
+    LazyLock::new(|| broadcast::channel(128).0);
 
 pub async fn init(
     service_name: &str,
     osc_port: u16,
-    mdns_sidecar_path: &str,
+    // No longer used now that mDNS advertising is native (see `advertise`); kept so existing
+    // callers don't need to change their call site.
+    _mdns_sidecar_path: &str,
 ) -> Result<(String, u16), Error> {
     // Ensure single initialization
     {
@@ -49,12 +68,6 @@ pub async fn init(
         let mut osc_port_ref = OSC_PORT.lock().await;
         *osc_port_ref = Some(osc_port);
     }
-    // Set the MDNS sidecar executable path
-    if let Err(e) = crate::mdns_sidecar::set_exe_path(mdns_sidecar_path.to_string()).await {
-        error!("Could not set the MDNS sidecar executable path: {:#?}", e);
-        *INITIALIZED.lock().await = false;
-        return Err(Error::InitError(e));
-    }
     // Initialize the OSCQuery service
     let (oscquery_host, oscquery_port, oscquery_shutdown_sender) =
         match start_oscquery_service().await {
@@ -78,6 +91,26 @@ pub async fn init(
         let mut oscquery_shutdown_sender_ref = OSCQUERY_SHUTDOWN_SENDER.lock().await;
         *oscquery_shutdown_sender_ref = Some(oscquery_shutdown_sender);
     }
+    // Initialize the WebSocket subscription service
+    let (ws_port, ws_shutdown_sender) = match start_ws_service().await {
+        Ok(result) => result,
+        Err(e) => {
+            error!("Could not start the OSCQuery WebSocket service: {:#?}", e);
+            *INITIALIZED.lock().await = false;
+            return Err(Error::InitError(
+                OSCQueryInitError::OSCQueryinitFailed,
+            ));
+        }
+    };
+    {
+        let mut ws_port_ref = WS_PORT.lock().await;
+        *ws_port_ref = Some(ws_port);
+        debug!("OSCQuery WebSocket Port: {}", ws_port);
+    }
+    {
+        let mut ws_shutdown_sender_ref = WS_SHUTDOWN_SENDER.lock().await;
+        *ws_shutdown_sender_ref = Some(ws_shutdown_sender);
+    }
     Ok((oscquery_host, oscquery_port))
 }
 
@@ -89,10 +122,15 @@ pub async fn deinit() -> Result<(), Error> {
             return Err(Error::InitError(OSCQueryInitError::NotYetInitialized));
         }
     }
-    // Stop the MDNS sidecar
-    if let Err(e) = crate::mdns_sidecar::mark_server_stopped().await {
-        error!("Could not stop the MDNS Sidecar: {:#?}", e);
-        return Err(Error::InitError(OSCQueryInitError::MDNSInitFailed));
+    // Withdraw the mDNS service records
+    {
+        let daemon = {
+            let guard = crate::MDNS_DAEMON.lock().await;
+            guard.clone()
+        };
+        if let Some(daemon) = daemon {
+            unregister_mdns_services(&daemon).await;
+        }
     }
     // Stop the OSC Query server
     {
@@ -101,10 +139,22 @@ pub async fn deinit() -> Result<(), Error> {
             sender.send(false).unwrap();
         }
     }
+    // Stop the WebSocket subscription service
+    {
+        let mut ws_shutdown_sender = WS_SHUTDOWN_SENDER.lock().await;
+        if let Some(sender) = ws_shutdown_sender.take() {
+            sender.send(false).unwrap();
+        }
+    }
+    // Stop tunneling to a relay, if we were
+    let _ = crate::expose::stop().await;
+    // Stop advertising through a relay, if we were
+    let _ = crate::relay::disconnect().await;
     // Reset state
     {
         *OSC_PORT.lock().await = None;
         *OSCQUERY_PORT.lock().await = None;
+        *WS_PORT.lock().await = None;
         *MDNS_SERVICE_NAME.lock().await = None;
         *OSCQUERY_ROOT_NODE.lock().await = None;
         *OSC_METHODS.lock().await = vec![];
@@ -143,20 +193,112 @@ pub async fn advertise() -> Result<(), Error> {
         let name = MDNS_SERVICE_NAME.lock().await;
         name.as_ref().unwrap().to_string()
     };
-    match crate::mdns_sidecar::mark_server_started(osc_port, oscquery_port, service_name).await {
-        Ok(_) => {}
-        Err(e) => {
-            error!("Failed to start MDNS sidecar: {:#?}", e);
-            return Err(Error::InitError(OSCQueryInitError::MDNSInitFailed));
+    crate::init_mdns_daemon().await?;
+    let daemon = {
+        let guard = crate::MDNS_DAEMON.lock().await;
+        guard
+            .clone()
+            .expect("MDNS daemon should be initialized by init_mdns_daemon")
+    };
+    let host_ip = local_ip_address::local_ip().map_err(Error::LocalIpUnavailable)?;
+    let host_name = format!("{}.local.", service_name.replace(' ', "-"));
+
+    // Re-advertising (e.g. after a port or name change) just unregisters the previous records
+    // and registers fresh ones; there's no sidecar process to restart anymore.
+    unregister_mdns_services(&daemon).await;
+
+    let oscquery_info = mdns_sd::ServiceInfo::new(
+        "_oscjson._tcp.local.",
+        &service_name,
+        &host_name,
+        host_ip,
+        oscquery_port,
+        None,
+    )
+    .map_err(|e| Error::InitError(OSCQueryInitError::MDNSDaemonInitFailed(e)))?;
+    let oscquery_fullname = oscquery_info.get_fullname().to_string();
+    daemon
+        .register(oscquery_info)
+        .map_err(|e| Error::InitError(OSCQueryInitError::MDNSDaemonInitFailed(e)))?;
+
+    let osc_info = mdns_sd::ServiceInfo::new(
+        "_osc._udp.local.",
+        &service_name,
+        &host_name,
+        host_ip,
+        osc_port,
+        None,
+    )
+    .map_err(|e| Error::InitError(OSCQueryInitError::MDNSDaemonInitFailed(e)))?;
+    let osc_fullname = osc_info.get_fullname().to_string();
+    daemon
+        .register(osc_info)
+        .map_err(|e| Error::InitError(OSCQueryInitError::MDNSDaemonInitFailed(e)))?;
+
+    *MDNS_REGISTERED_OSCQUERY.lock().await = Some(oscquery_fullname);
+    *MDNS_REGISTERED_OSC.lock().await = Some(osc_fullname);
+    Ok(())
+}
+
+async fn unregister_mdns_services(daemon: &mdns_sd::ServiceDaemon) {
+    if let Some(fullname) = MDNS_REGISTERED_OSCQUERY.lock().await.take() {
+        let _ = daemon.unregister(&fullname);
+    }
+    if let Some(fullname) = MDNS_REGISTERED_OSC.lock().await.take() {
+        let _ = daemon.unregister(&fullname);
+    }
+}
+
+/// Additionally publish this server's HostInfo and node tree through a WebSocket relay, so it
+/// can be discovered and driven by a peer outside the LAN alongside the regular mDNS
+/// advertisement. Returns the address/slug the relay assigned this session.
+pub async fn advertise_relay(relay_url: &str) -> Result<String, Error> {
+    {
+        let initialized = INITIALIZED.lock().await;
+        if !*initialized {
+            return Err(Error::InitError(OSCQueryInitError::NotYetInitialized));
         }
     }
-    Ok(())
+    crate::relay::connect(relay_url).await
+}
+
+/// Expose the loopback-bound OSCQuery HTTP server and OSC UDP port to a remote peer by tunneling
+/// them through an outbound WebSocket connection to a relay (see [`crate::expose`]). Unlike
+/// [`advertise_relay`], the relay forwards raw TCP/UDP traffic rather than OSCQuery requests, so
+/// the remote peer talks to what looks like a normal local OSCQuery/OSC pair. Returns the
+/// address/slug the relay assigned this session.
+pub async fn expose_via_relay(relay_ws_addr: Option<&str>) -> Result<String, Error> {
+    let (osc_port, oscquery_port) = {
+        let initialized = INITIALIZED.lock().await;
+        if !*initialized {
+            return Err(Error::InitError(OSCQueryInitError::NotYetInitialized));
+        }
+        let osc_port = OSC_PORT.lock().await.ok_or(Error::InitError(OSCQueryInitError::NotYetInitialized))?;
+        let oscquery_port = OSCQUERY_PORT
+            .lock()
+            .await
+            .ok_or(Error::InitError(OSCQueryInitError::NotYetInitialized))?;
+        (osc_port, oscquery_port)
+    };
+    crate::expose::start(relay_ws_addr, oscquery_port, osc_port).await
 }
 
 //
 // Managing OSC Methods
 //
 
+/// Snapshot of the currently registered OSC methods, for subsystems (e.g. the MQTT bridge) that
+/// need to mirror them elsewhere without holding the server's internal lock themselves.
+pub(crate) async fn get_osc_methods() -> Vec<OSCMethod> {
+    let osc_methods = OSC_METHODS.lock().await;
+    osc_methods.clone()
+}
+
+pub(crate) async fn get_osc_port() -> Option<u16> {
+    let osc_port = OSC_PORT.lock().await;
+    *osc_port
+}
+
 pub async fn add_osc_method(ad: OSCMethod) {
     {
         let mut osc_methods = OSC_METHODS.lock().await;
@@ -221,6 +363,10 @@ pub async fn set_osc_method_value(full_address: String, value: Option<String>) {
         method.value = value;
         drop(osc_methods);
         update_oscquery_root_node().await;
+        // Notify any WebSocket connections subscribed to this address
+        if let Some(node) = get_node_for_osc_address(full_address.clone()).await {
+            let _ = OSC_VALUE_UPDATES.send((full_address, node.value));
+        }
     }
 }
 
@@ -334,56 +480,183 @@ async fn start_oscquery_service() -> Result<(String, u16, Sender<bool>), Error>
 
 async fn handle_oscquery_request(
     req: Request<hyper::body::Incoming>,
-) -> Result<Response<Full<Bytes>>, Infallible> {
+) -> Result<Response<ResponseBody>, Infallible> {
     // Get path from request
     let path = req.uri().path().to_string();
-    let json = get_json_for_osc_address(path.clone()).await;
     // Get query parameters from request
     let query = req.uri().query();
     if let Some(query) = query {
         match query {
             "HOST_INFO" => {
-                let mut response =
-                    Response::new(Full::new(Bytes::from(get_host_info_json().await)));
+                let mut response = Response::new(ResponseBody::buffered(get_host_info_json().await));
                 let headers = response.headers_mut();
                 headers.insert("Access-Control-Allow-Origin", "*".parse().unwrap());
                 headers.insert("Content-Type", "application/json".parse().unwrap());
                 return Ok(response);
             }
             _ => {
-                let mut response = Response::new(Full::new(Bytes::from("Unknown Attribute")));
+                let mut response = ResponseBody::buffered("Unknown Attribute").into_response();
                 *response.status_mut() = hyper::StatusCode::NO_CONTENT;
                 return Ok(response);
             }
         }
     }
-    match json {
-        Some(json) => {
-            let mut response = Response::new(Full::new(Bytes::from(json)));
+    match get_node_for_osc_address(path).await {
+        Some(node) => {
+            let mut response = Response::new(ResponseBody::Node(NodeBody::new(node)));
             let headers = response.headers_mut();
             headers.insert("Access-Control-Allow-Origin", "*".parse().unwrap());
             headers.insert("Content-Type", "application/json".parse().unwrap());
             Ok(response)
         }
         None => {
-            let mut response = Response::new(Full::new(Bytes::from("No Content")));
+            let mut response = ResponseBody::buffered("No Content").into_response();
             *response.status_mut() = hyper::StatusCode::NO_CONTENT;
             Ok(response)
         }
     }
 }
 
+//
+// Streaming HTTP response bodies
+//
+
+/// The body type served by [`handle_oscquery_request`]: either a small buffered response (errors,
+/// `HOST_INFO`), or a [`NodeBody`] that streams a namespace node's JSON one fragment at a time.
+pub(crate) enum ResponseBody {
+    Buffered(Full<Bytes>),
+    Node(NodeBody),
+}
+
+impl ResponseBody {
+    fn buffered(body: impl Into<Bytes>) -> Self {
+        ResponseBody::Buffered(Full::new(body.into()))
+    }
+
+    fn into_response(self) -> Response<ResponseBody> {
+        Response::new(self)
+    }
+}
+
+impl HttpBody for ResponseBody {
+    type Data = Bytes;
+    type Error = Infallible;
+
+    fn poll_frame(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<Frame<Self::Data>, Self::Error>>> {
+        match self.get_mut() {
+            ResponseBody::Buffered(body) => Pin::new(body).poll_frame(cx),
+            ResponseBody::Node(body) => Pin::new(body).poll_frame(cx),
+        }
+    }
+}
+
+/// One pending piece of a [`NodeBody`]'s depth-first JSON walk.
+enum NodeFragment {
+    /// Emit this text verbatim.
+    Text(Cow<'static, str>),
+    /// Expand this node's own fields (and queue its children) the next time it's popped.
+    Node(OSCQueryNode),
+}
+
+/// A response body that serializes an [`OSCQueryNode`] one `Frame` at a time instead of
+/// rendering the whole tree into a single buffer up front. Each poll renders the scalar fields of
+/// one node only; nodes not yet emitted wait on the stack as `OSCQueryNode` values, so the fully
+/// rendered JSON string is never held in memory.
+pub(crate) struct NodeBody {
+    stack: Vec<NodeFragment>,
+}
+
+impl NodeBody {
+    fn new(node: OSCQueryNode) -> Self {
+        Self {
+            stack: vec![NodeFragment::Node(node)],
+        }
+    }
+
+    /// Renders `node`'s own scalar fields into a single chunk, and -- if it has children --
+    /// pushes its `CONTENTS` entries (and closing punctuation) onto `stack` for later polls.
+    fn expand(node: OSCQueryNode, stack: &mut Vec<NodeFragment>) -> Bytes {
+        let mut out = String::from("{");
+        if let Some(description) = &node.description {
+            out.push_str("\"DESCRIPTION\":");
+            out.push_str(&serde_json::to_string(description).unwrap());
+            out.push(',');
+        }
+        out.push_str("\"FULL_PATH\":");
+        out.push_str(&serde_json::to_string(&node.full_path).unwrap());
+        out.push_str(",\"ACCESS\":");
+        out.push_str(&node.access.to_string());
+        if let Some(value_type) = &node.value_type {
+            out.push_str(",\"TYPE\":");
+            out.push_str(&serde_json::to_string(value_type).unwrap());
+        }
+        if !node.value.is_empty() {
+            out.push_str(",\"VALUE\":");
+            out.push_str(&serde_json::to_string(&node.value).unwrap());
+        }
+
+        if node.contents.is_empty() {
+            out.push('}');
+            return Bytes::from(out);
+        }
+        out.push_str(",\"CONTENTS\":{");
+
+        // Lay out the children (and the closing braces) in the order they should be emitted,
+        // then push them onto the LIFO `stack` in reverse so they pop back out in that order.
+        let entries: Vec<_> = node.contents.into_iter().collect();
+        let last = entries.len().saturating_sub(1);
+        let mut tail = Vec::with_capacity(entries.len() * 2 + 1);
+        for (index, (key, child)) in entries.into_iter().enumerate() {
+            tail.push(NodeFragment::Text(Cow::Owned(format!(
+                "{}:",
+                serde_json::to_string(&key).unwrap()
+            ))));
+            tail.push(NodeFragment::Node(child));
+            if index != last {
+                tail.push(NodeFragment::Text(Cow::Borrowed(",")));
+            }
+        }
+        tail.push(NodeFragment::Text(Cow::Borrowed("}}")));
+        stack.extend(tail.into_iter().rev());
+
+        Bytes::from(out)
+    }
+}
+
+impl HttpBody for NodeBody {
+    type Data = Bytes;
+    type Error = Infallible;
+
+    fn poll_frame(
+        mut self: Pin<&mut Self>,
+        _cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<Frame<Self::Data>, Self::Error>>> {
+        match self.stack.pop() {
+            Some(NodeFragment::Text(text)) => {
+                Poll::Ready(Some(Ok(Frame::data(Bytes::from(text.into_owned())))))
+            }
+            Some(NodeFragment::Node(node)) => {
+                let stack = &mut self.stack;
+                Poll::Ready(Some(Ok(Frame::data(Self::expand(node, stack)))))
+            }
+            None => Poll::Ready(None),
+        }
+    }
+}
+
 //
 // Generate (JSON) Request Output
 //
 
-async fn get_json_for_osc_address(address: String) -> Option<String> {
+/// Walks the OSCQuery node tree down to `address`, e.g. for serving an HTTP GET or answering a
+/// relay peer's query for the same node.
+pub(crate) async fn get_node_for_osc_address(address: String) -> Option<OSCQueryNode> {
     let root_query_node = {
         let root_query_node = OSCQUERY_ROOT_NODE.lock().await;
-        match root_query_node.clone() {
-            Some(node) => node,
-            None => return None,
-        }
+        root_query_node.clone()?
     };
     let address_parts = address.split('/');
     let mut current_node = &root_query_node;
@@ -397,30 +670,156 @@ async fn get_json_for_osc_address(address: String) -> Option<String> {
             return None;
         }
     }
-    Some(serde_json::to_string(&current_node).unwrap())
+    Some(current_node.clone())
 }
 
-async fn get_host_info_json() -> String {
-    let service_name = {
-        let name = MDNS_SERVICE_NAME.lock().await;
-        name.as_ref().unwrap().to_string()
-    };
-    let osc_port = {
-        let osc_port = OSC_PORT.lock().await;
-        *osc_port
-            .as_ref()
-            .expect("Tried to get the host info before initialization.")
-    };
-    let host_info = OSCQueryHostInfo {
+/// The host info this server currently advertises, or `None` before [`init`] has completed.
+pub(crate) async fn get_host_info() -> Option<OSCQueryHostInfo> {
+    let service_name = MDNS_SERVICE_NAME.lock().await.clone()?;
+    let osc_port = (*OSC_PORT.lock().await)?;
+    let ws_port = (*WS_PORT.lock().await)?;
+    Some(OSCQueryHostInfo {
         name: service_name,
         osc_transport: "UDP".to_string(),
         osc_ip: "127.0.0.1".to_string(),
         osc_port,
+        ws_ip: Some("127.0.0.1".to_string()),
+        ws_port: Some(ws_port),
         extensions: HashMap::from([
             ("ACCESS".to_string(), true),
             ("VALUE".to_string(), true),
             ("DESCRIPTION".to_string(), true),
         ]),
+    })
+}
+
+pub(crate) async fn get_host_info_json() -> String {
+    serde_json::to_string(
+        &get_host_info()
+            .await
+            .expect("Tried to get the host info before initialization."),
+    )
+    .unwrap()
+}
+
+//
+// WebSocket subscription (LISTEN/IGNORE) server
+//
+
+async fn start_ws_service() -> Result<(u16, Sender<bool>), Error> {
+    let addr = SocketAddr::from(([127, 0, 0, 1], 0));
+    let listener = match TcpListener::bind(addr).await {
+        Ok(listener) => listener,
+        Err(e) => {
+            return Err(Error::IO(e));
+        }
     };
-    serde_json::to_string(&host_info).unwrap()
+    let port = listener.local_addr().unwrap().port();
+
+    let (shutdown_sender, mut shutdown_receiver) = channel::<bool>(true);
+
+    tokio::task::spawn(async move {
+        loop {
+            tokio::select! {
+                accept_result = listener.accept() => {
+                    match accept_result {
+                        Ok((stream, _)) => {
+                            let shutdown_receiver = shutdown_receiver.clone();
+                            tokio::task::spawn(handle_ws_connection(stream, shutdown_receiver));
+                        }
+                        Err(_) => {
+                            continue;
+                        }
+                    }
+                }
+                _ = shutdown_receiver.changed() => {
+                    // Shutdown signal received
+                    break;
+                }
+            }
+        }
+    });
+    Ok((port, shutdown_sender))
+}
+
+async fn handle_ws_connection(
+    stream: tokio::net::TcpStream,
+    mut shutdown_receiver: tokio::sync::watch::Receiver<bool>,
+) {
+    let ws_stream = match accept_async(stream).await {
+        Ok(ws_stream) => ws_stream,
+        Err(e) => {
+            debug!("Failed to complete WebSocket handshake: {}", e);
+            return;
+        }
+    };
+    let (mut write, mut read) = ws_stream.split();
+    let mut subscriptions: HashSet<String> = HashSet::new();
+    let mut value_updates = OSC_VALUE_UPDATES.subscribe();
+
+    loop {
+        tokio::select! {
+            message = read.next() => {
+                match message {
+                    Some(Ok(Message::Text(text))) => {
+                        if !handle_ws_command(&text, &mut subscriptions).await {
+                            break;
+                        }
+                    }
+                    Some(Ok(Message::Close(_))) | None => break,
+                    Some(Ok(_)) => {}
+                    Some(Err(e)) => {
+                        debug!("WebSocket connection error: {}", e);
+                        break;
+                    }
+                }
+            }
+            update = value_updates.recv() => {
+                match update {
+                    Ok((address, value)) => {
+                        if subscriptions.contains(&address) {
+                            let frame = serde_json::json!({ "FULL_PATH": address, "VALUE": value });
+                            if write.send(Message::Text(frame.to_string())).await.is_err() {
+                                break;
+                            }
+                        }
+                    }
+                    Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                    Err(broadcast::error::RecvError::Closed) => break,
+                }
+            }
+            _ = shutdown_receiver.changed() => break,
+        }
+    }
+    // Connection is gone; drop its subscriptions along with it.
+    subscriptions.clear();
+}
+
+/// Handles one `{"COMMAND":"LISTEN"|"IGNORE","DATA":"/some/address"}` frame. Returns `false` if
+/// the connection should be closed (malformed frame).
+async fn handle_ws_command(text: &str, subscriptions: &mut HashSet<String>) -> bool {
+    #[derive(serde::Deserialize)]
+    struct WsCommand {
+        #[serde(rename = "COMMAND")]
+        command: String,
+        #[serde(rename = "DATA")]
+        data: String,
+    }
+    let command: WsCommand = match serde_json::from_str(text) {
+        Ok(command) => command,
+        Err(_) => return false,
+    };
+    match command.command.as_str() {
+        "LISTEN" => {
+            // Ignore LISTEN for addresses that don't resolve to a known node
+            if get_node_for_osc_address(command.data.clone()).await.is_some() {
+                subscriptions.insert(command.data);
+            }
+        }
+        "IGNORE" => {
+            subscriptions.remove(&command.data);
+        }
+        _ => {}
+    }
+    true
 }
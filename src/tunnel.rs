@@ -0,0 +1,343 @@
+//! Forwards OSC (UDP) traffic to/from a remote peer over an encrypted QUIC connection, so
+//! VRChat's OSC stream can be relayed across the internet without exposing a raw UDP socket.
+//! Neither end verifies the other's identity (see `build_client_config`).
+
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::sync::LazyLock;
+use std::time::Duration;
+
+use log::{debug, error};
+use quinn::{ClientConfig, Endpoint, RecvStream, ServerConfig};
+use tokio::io::AsyncWriteExt;
+use tokio::net::UdpSocket;
+use tokio::sync::watch;
+use tokio::sync::Mutex;
+
+use crate::models::{Error, ForwardDirection, ForwardProtocol};
+
+// A QUIC datagram this size comfortably fits an OSC packet without fragmenting; anything larger
+// is sent as a short unidirectional stream instead.
+const MAX_DATAGRAM_SIZE: usize = 1200;
+const ALPN: &[u8] = b"oyasumivr-oscquery-tunnel";
+
+const RECONNECT_BACKOFF: [Duration; 5] = [
+    Duration::from_secs(1),
+    Duration::from_secs(2),
+    Duration::from_secs(5),
+    Duration::from_secs(10),
+    Duration::from_secs(30),
+];
+
+static SHUTDOWN_SENDER: LazyLock<Mutex<Option<watch::Sender<bool>>>> = LazyLock::new(|| Mutex::default());
+
+/// Listen for an incoming QUIC connection on `bind_addr` and forward OSC traffic between it and
+/// `local_osc_addr` in the given `direction`.
+pub async fn listen(
+    direction: ForwardDirection,
+    protocol: ForwardProtocol,
+    bind_addr: SocketAddr,
+    local_osc_addr: SocketAddr,
+) -> Result<(), Error> {
+    let ForwardProtocol::Udp = protocol;
+    let server_config = build_server_config()?;
+    let endpoint = Endpoint::server(server_config, bind_addr).map_err(|e| {
+        Error::IO(std::io::Error::new(std::io::ErrorKind::Other, e))
+    })?;
+    let (shutdown_sender, shutdown_receiver) = watch::channel(false);
+    {
+        let mut sender_ref = SHUTDOWN_SENDER.lock().await;
+        *sender_ref = Some(shutdown_sender);
+    }
+    tokio::spawn(accept_loop(endpoint, direction, local_osc_addr, shutdown_receiver));
+    Ok(())
+}
+
+/// Dial out to a remote peer already listening via [`listen`] and forward OSC traffic between
+/// the resulting QUIC connection and `local_osc_addr` in the given `direction`, reconnecting
+/// with backoff if the connection is ever lost.
+pub async fn connect(
+    direction: ForwardDirection,
+    protocol: ForwardProtocol,
+    remote_addr: SocketAddr,
+    server_name: &str,
+    local_osc_addr: SocketAddr,
+) -> Result<(), Error> {
+    let ForwardProtocol::Udp = protocol;
+    let client_config = build_client_config();
+    let mut endpoint = Endpoint::client(SocketAddr::from(([0, 0, 0, 0], 0)))
+        .map_err(|e| Error::IO(std::io::Error::new(std::io::ErrorKind::Other, e)))?;
+    endpoint.set_default_client_config(client_config);
+    let connection = dial(&endpoint, remote_addr, server_name).await?;
+    let (shutdown_sender, shutdown_receiver) = watch::channel(false);
+    {
+        let mut sender_ref = SHUTDOWN_SENDER.lock().await;
+        *sender_ref = Some(shutdown_sender);
+    }
+    tokio::spawn(run_client_session(
+        endpoint,
+        remote_addr,
+        server_name.to_string(),
+        direction,
+        local_osc_addr,
+        connection,
+        shutdown_receiver,
+    ));
+    Ok(())
+}
+
+async fn dial(
+    endpoint: &Endpoint,
+    remote_addr: SocketAddr,
+    server_name: &str,
+) -> Result<quinn::Connection, Error> {
+    let connecting = endpoint
+        .connect(remote_addr, server_name)
+        .map_err(|e| Error::IO(std::io::Error::new(std::io::ErrorKind::Other, e)))?;
+    let connection = connecting
+        .await
+        .map_err(|e| Error::IO(std::io::Error::new(std::io::ErrorKind::Other, e)))?;
+    debug!("QUIC tunnel connected to {}", remote_addr);
+    Ok(connection)
+}
+
+/// Drives a dialed-out connection until it's lost, then keeps redialing `remote_addr` with
+/// backoff until [`stop`] is called. Mirrors [`crate::relay`]'s reconnect loop for the same
+/// "peer went away, keep retrying" shape.
+async fn run_client_session(
+    endpoint: Endpoint,
+    remote_addr: SocketAddr,
+    server_name: String,
+    direction: ForwardDirection,
+    local_osc_addr: SocketAddr,
+    mut connection: quinn::Connection,
+    mut shutdown_receiver: watch::Receiver<bool>,
+) {
+    let mut backoff_index = 0usize;
+    loop {
+        match run_connection(connection, direction, local_osc_addr, shutdown_receiver.clone()).await {
+            ConnectionExit::Shutdown => return,
+            ConnectionExit::Lost => {}
+        }
+
+        loop {
+            let delay = RECONNECT_BACKOFF[backoff_index.min(RECONNECT_BACKOFF.len() - 1)];
+            backoff_index += 1;
+            tokio::select! {
+                _ = tokio::time::sleep(delay) => {}
+                _ = shutdown_receiver.changed() => return,
+            }
+            match dial(&endpoint, remote_addr, &server_name).await {
+                Ok(new_connection) => {
+                    connection = new_connection;
+                    backoff_index = 0;
+                    break;
+                }
+                Err(e) => error!("Failed to reconnect QUIC tunnel to {}, retrying: {:?}", remote_addr, e),
+            }
+        }
+    }
+}
+
+pub async fn stop() -> Result<(), Error> {
+    let mut sender_ref = SHUTDOWN_SENDER.lock().await;
+    if let Some(sender) = sender_ref.take() {
+        let _ = sender.send(true);
+    }
+    Ok(())
+}
+
+async fn accept_loop(
+    endpoint: Endpoint,
+    direction: ForwardDirection,
+    local_osc_addr: SocketAddr,
+    mut shutdown_receiver: watch::Receiver<bool>,
+) {
+    loop {
+        tokio::select! {
+            incoming = endpoint.accept() => {
+                let Some(incoming) = incoming else { break };
+                match incoming.await {
+                    Ok(connection) => {
+                        debug!("QUIC tunnel accepted connection from {}", connection.remote_address());
+                        tokio::spawn(run_connection(connection, direction, local_osc_addr, shutdown_receiver.clone()));
+                    }
+                    Err(e) => error!("Failed to accept QUIC tunnel connection: {}", e),
+                }
+            }
+            _ = shutdown_receiver.changed() => break,
+        }
+    }
+}
+
+/// Why [`run_connection`] stopped pumping a connection: either the caller asked us to ([`stop`]
+/// fired), or the socket/connection dropped out from under us and the caller should reconnect.
+enum ConnectionExit {
+    Shutdown,
+    Lost,
+}
+
+/// Pumps OSC datagrams between a single QUIC connection and the local OSC UDP port, replaying
+/// bytes verbatim in both directions so `rosc::decoder::decode_udp` still works unmodified on
+/// the receiving end.
+async fn run_connection(
+    connection: quinn::Connection,
+    direction: ForwardDirection,
+    local_osc_addr: SocketAddr,
+    mut shutdown_receiver: watch::Receiver<bool>,
+) -> ConnectionExit {
+    // In `LocalToRemote` mode we're capturing OSC traffic emitted locally, which means we must
+    // bind `local_osc_addr` itself to receive it. In `RemoteToLocal` mode `local_osc_addr` is the
+    // destination we forward *to*, so an ephemeral port is all we need to send from.
+    let bind_addr = match direction {
+        ForwardDirection::LocalToRemote => local_osc_addr,
+        ForwardDirection::RemoteToLocal => SocketAddr::from(([127, 0, 0, 1], 0)),
+    };
+    let socket = match UdpSocket::bind(bind_addr).await {
+        Ok(socket) => Arc::new(socket),
+        Err(e) => {
+            error!("Failed to bind local OSC relay socket on {}: {}", bind_addr, e);
+            return ConnectionExit::Lost;
+        }
+    };
+
+    let mut buf = [0u8; 65536];
+    loop {
+        tokio::select! {
+            // Local OSC traffic -> remote peer
+            received = socket.recv_from(&mut buf), if direction == ForwardDirection::LocalToRemote => {
+                match received {
+                    Ok((len, _)) => send_packet(&connection, &buf[..len]).await,
+                    Err(e) => {
+                        error!("Lost local OSC socket while tunneling: {}", e);
+                        return ConnectionExit::Lost;
+                    }
+                }
+            }
+            // Remote peer traffic -> local OSC port
+            datagram = connection.read_datagram(), if direction == ForwardDirection::RemoteToLocal => {
+                match datagram {
+                    Ok(data) => { let _ = socket.send_to(&data, local_osc_addr).await; }
+                    Err(e) => {
+                        error!("QUIC tunnel connection lost: {}", e);
+                        return ConnectionExit::Lost;
+                    }
+                }
+            }
+            stream = connection.accept_uni(), if direction == ForwardDirection::RemoteToLocal => {
+                match stream {
+                    Ok(recv) => {
+                        tokio::spawn(relay_uni_stream(recv, socket.clone(), local_osc_addr));
+                    }
+                    Err(e) => {
+                        error!("QUIC tunnel connection lost: {}", e);
+                        return ConnectionExit::Lost;
+                    }
+                }
+            }
+            // Catches a lost connection in `LocalToRemote` mode too, where nothing reads from it
+            reason = connection.closed() => {
+                error!("QUIC tunnel connection lost: {}", reason);
+                return ConnectionExit::Lost;
+            }
+            _ = shutdown_receiver.changed() => return ConnectionExit::Shutdown,
+        }
+    }
+}
+
+async fn relay_uni_stream(mut recv: RecvStream, socket: Arc<UdpSocket>, local_osc_addr: SocketAddr) {
+    if let Ok(Some(data)) = recv.read_to_end(65536).await.map(Some) {
+        let _ = socket.send_to(&data, local_osc_addr).await;
+    }
+}
+
+async fn send_packet(connection: &quinn::Connection, data: &[u8]) {
+    if data.len() <= MAX_DATAGRAM_SIZE {
+        if let Err(e) = connection.send_datagram(data.to_vec().into()) {
+            error!("Failed to send OSC packet over QUIC tunnel: {}", e);
+        }
+        return;
+    }
+    // Packet is too large for a single datagram; fall back to a short unidirectional stream.
+    match connection.open_uni().await {
+        Ok(mut send) => {
+            if let Err(e) = send.write_all(data).await {
+                error!("Failed to stream oversized OSC packet over QUIC tunnel: {}", e);
+            }
+            let _ = send.finish();
+        }
+        Err(e) => error!("Failed to open QUIC stream for oversized OSC packet: {}", e),
+    }
+}
+
+fn build_server_config() -> Result<ServerConfig, Error> {
+    let cert = rcgen::generate_simple_self_signed(vec!["oyasumivr-oscquery-tunnel".to_string()])
+        .map_err(|e| Error::IO(std::io::Error::new(std::io::ErrorKind::Other, e.to_string())))?;
+    let cert_der = rustls::pki_types::CertificateDer::from(cert.cert);
+    let key_der = rustls::pki_types::PrivateKeyDer::try_from(cert.key_pair.serialize_der())
+        .map_err(|e| Error::IO(std::io::Error::new(std::io::ErrorKind::Other, e)))?;
+    let mut crypto = rustls::ServerConfig::builder()
+        .with_no_client_auth()
+        .with_single_cert(vec![cert_der], key_der)
+        .map_err(|e| Error::IO(std::io::Error::new(std::io::ErrorKind::Other, e.to_string())))?;
+    // QUIC requires ALPN; without it the TLS handshake aborts with no_application_protocol.
+    crypto.alpn_protocols = vec![ALPN.to_vec()];
+    let quic_crypto = quinn::crypto::rustls::QuicServerConfig::try_from(crypto)
+        .map_err(|e| Error::IO(std::io::Error::new(std::io::ErrorKind::Other, e.to_string())))?;
+    Ok(ServerConfig::with_crypto(Arc::new(quic_crypto)))
+}
+
+/// Both ends of a forward are paired out of band (the user supplies each other's address), so
+/// the client trusts whatever certificate the peer presents rather than validating it against a
+/// CA. The channel is encrypted, but the peer's identity is not verified.
+fn build_client_config() -> ClientConfig {
+    let mut crypto = rustls::ClientConfig::builder()
+        .dangerous()
+        .with_custom_certificate_verifier(Arc::new(AcceptAnyServerCert))
+        .with_no_client_auth();
+    // QUIC requires ALPN; without it the TLS handshake aborts with no_application_protocol.
+    crypto.alpn_protocols = vec![ALPN.to_vec()];
+    ClientConfig::new(Arc::new(
+        quinn::crypto::rustls::QuicClientConfig::try_from(crypto).unwrap(),
+    ))
+}
+
+#[derive(Debug)]
+struct AcceptAnyServerCert;
+
+impl rustls::client::danger::ServerCertVerifier for AcceptAnyServerCert {
+    fn verify_server_cert(
+        &self,
+        _end_entity: &rustls::pki_types::CertificateDer<'_>,
+        _intermediates: &[rustls::pki_types::CertificateDer<'_>],
+        _server_name: &rustls::pki_types::ServerName<'_>,
+        _ocsp_response: &[u8],
+        _now: rustls::pki_types::UnixTime,
+    ) -> Result<rustls::client::danger::ServerCertVerified, rustls::Error> {
+        Ok(rustls::client::danger::ServerCertVerified::assertion())
+    }
+
+    fn verify_tls12_signature(
+        &self,
+        _message: &[u8],
+        _cert: &rustls::pki_types::CertificateDer<'_>,
+        _dss: &rustls::DigitallySignedStruct,
+    ) -> Result<rustls::client::danger::HandshakeSignatureValid, rustls::Error> {
+        Ok(rustls::client::danger::HandshakeSignatureValid::assertion())
+    }
+
+    fn verify_tls13_signature(
+        &self,
+        _message: &[u8],
+        _cert: &rustls::pki_types::CertificateDer<'_>,
+        _dss: &rustls::DigitallySignedStruct,
+    ) -> Result<rustls::client::danger::HandshakeSignatureValid, rustls::Error> {
+        Ok(rustls::client::danger::HandshakeSignatureValid::assertion())
+    }
+
+    fn supported_verify_schemes(&self) -> Vec<rustls::SignatureScheme> {
+        rustls::crypto::ring::default_provider()
+            .signature_verification_algorithms
+            .supported_schemes()
+    }
+}